@@ -26,8 +26,10 @@ use sp_runtime::{
 	traits::{BlakeTwo256, Block as BlockT, UniqueSaturatedInto},
 };
 use sqlx::{
-	sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions, SqliteQueryResult},
-	ConnectOptions, Error, QueryBuilder, Row, Sqlite,
+	any::{AnyConnectOptions, AnyPool, AnyPoolOptions, AnyQueryResult},
+	postgres::PgConnectOptions,
+	sqlite::SqliteConnectOptions,
+	Any, ConnectOptions, Error, QueryBuilder, Row,
 };
 use std::{str::FromStr, sync::Arc};
 
@@ -44,19 +46,68 @@ pub struct Log {
 	pub substrate_block_hash: Vec<u8>,
 }
 
+/// A result row of a log filter query: enough to locate the log in the
+/// substrate backend and serve it back through `fc-rpc`.
+#[derive(Debug, Eq, PartialEq)]
+pub struct FilteredLog {
+	pub substrate_block_hash: H256,
+	pub transaction_index: i32,
+	pub log_index: i32,
+}
+
 pub struct SqliteBackendConfig<'a> {
 	pub path: &'a str,
 	pub create_if_missing: bool,
 }
 
+/// An ethereum block hash to substrate block hash mapping row.
+struct BlockMapping {
+	ethereum_block_hash: Vec<u8>,
+	substrate_block_hash: Vec<u8>,
+}
+
+/// An ethereum transaction hash to its enclosing block and position.
+struct TransactionMapping {
+	ethereum_transaction_hash: Vec<u8>,
+	substrate_block_hash: Vec<u8>,
+	ethereum_block_hash: Vec<u8>,
+	transaction_index: i32,
+}
+
+/// Everything `spawn_logs_task_inner` extracts from a batch of substrate
+/// blocks: the logs to index plus the hash-lookup mappings.
+#[derive(Default)]
+struct IndexedBlocks {
+	logs: Vec<Log>,
+	block_mappings: Vec<BlockMapping>,
+	transaction_mappings: Vec<TransactionMapping>,
+}
+
+pub struct PostgresBackendConfig<'a> {
+	pub url: &'a str,
+}
+
 pub enum BackendConfig<'a> {
 	Sqlite(SqliteBackendConfig<'a>),
+	Postgres(PostgresBackendConfig<'a>),
+}
+
+impl<'a> BackendConfig<'a> {
+	fn is_postgres(&self) -> bool {
+		matches!(self, BackendConfig::Postgres(_))
+	}
 }
 
 #[derive(Clone)]
 pub struct Backend<Block: BlockT> {
-	pool: SqlitePool,
+	pool: AnyPool,
 	overrides: Arc<OverrideHandle<Block>>,
+	/// Whether `pool` was opened against Postgres rather than SQLite. Used to
+	/// reject the [`crate::BackendReader`] sync read paths, which block on a
+	/// bare `futures::executor::block_on` with no tokio reactor driving it —
+	/// fine for SQLite's blocking-threadpool driver, but the Postgres driver's
+	/// socket I/O needs that reactor and would hang rather than complete.
+	is_postgres: bool,
 }
 impl<Block: BlockT> Backend<Block>
 where
@@ -67,45 +118,176 @@ where
 		pool_size: u32,
 		overrides: Arc<OverrideHandle<Block>>,
 	) -> Result<Self, Error> {
-		let any_pool = SqlitePoolOptions::new()
+		// `AnyPool` dispatches to a driver registered in the process-wide
+		// driver registry; register the drivers compiled into sqlx before the
+		// first connection is established (idempotent across calls).
+		sqlx::any::install_default_drivers();
+		let is_postgres = config.is_postgres();
+		let any_pool = AnyPoolOptions::new()
 			.max_connections(pool_size)
-			.connect_lazy_with(
-				Self::connect_options(&config)?
-					.disable_statement_logging()
-					.clone(),
-			);
-		let _ = Self::create_if_not_exists(&any_pool).await?;
+			.connect_lazy_with(Self::connect_options(&config)?);
+		Self::create_if_not_exists(&any_pool, is_postgres).await?;
 		Ok(Self {
 			pool: any_pool,
 			overrides,
+			is_postgres,
 		})
 	}
 
-	fn connect_options(config: &BackendConfig) -> Result<SqliteConnectOptions, Error> {
+	fn connect_options(config: &BackendConfig) -> Result<AnyConnectOptions, Error> {
 		match config {
 			BackendConfig::Sqlite(config) => {
-				let config = sqlx::sqlite::SqliteConnectOptions::from_str(config.path)?
+				let options = SqliteConnectOptions::from_str(config.path)?
 					.create_if_missing(config.create_if_missing)
-					.into();
-				Ok(config)
+					.disable_statement_logging()
+					.clone();
+				Ok(options.into())
+			}
+			BackendConfig::Postgres(config) => {
+				let options = PgConnectOptions::from_str(config.url)?
+					.disable_statement_logging()
+					.clone();
+				Ok(options.into())
 			}
 		}
 	}
 
-	pub fn pool(&self) -> &SqlitePool {
+	pub fn pool(&self) -> &AnyPool {
 		&self.pool
 	}
 
-	pub async fn insert_sync_status(&self, hashes: &Vec<H256>) -> Result<SqliteQueryResult, Error> {
-		let mut builder: QueryBuilder<Sqlite> =
+	pub async fn insert_sync_status(&self, hashes: &Vec<H256>) -> Result<AnyQueryResult, Error> {
+		let mut builder: QueryBuilder<Any> =
 			QueryBuilder::new("INSERT INTO sync_status(substrate_block_hash) ");
 		builder.push_values(hashes, |mut b, hash| {
-			b.push_bind(hash.as_bytes());
+			b.push_bind(hash.as_bytes().to_vec());
 		});
+		// Enqueuing is idempotent: a block already queued (e.g. re-visited by
+		// the backfill walk on restart) is simply skipped.
+		builder.push(" ON CONFLICT DO NOTHING");
 		let query = builder.build();
 		query.execute(self.pool()).await
 	}
 
+	/// Serve an `eth_getLogs` filter straight from the `logs` index.
+	///
+	/// The filter is translated into a single parameterized query against the
+	/// `logs` table and its `block_number`/`topic_N` indexes: a block range,
+	/// an optional address set, and up to four positional topic sets. Results
+	/// are ordered by `(block_number, transaction_index, log_index)` so they
+	/// are deterministic, and capped at `max_past_logs` — exceeding the cap is
+	/// an error rather than a silent truncation.
+	pub async fn filter_logs(
+		&self,
+		from_block: i32,
+		to_block: i32,
+		addresses: Vec<Vec<u8>>,
+		topics: Vec<Vec<Vec<u8>>>,
+		max_past_logs: u32,
+	) -> Result<Vec<FilteredLog>, Error> {
+		// No `DISTINCT` here: `logs` is unique on `(substrate_block_hash,
+		// transaction_index, log_index)`, so this select list can never
+		// produce duplicate rows on its own — and `DISTINCT` without every
+		// `ORDER BY` expression in the select list is rejected by Postgres.
+		let mut builder: QueryBuilder<Any> = QueryBuilder::new(
+			"SELECT substrate_block_hash, transaction_index, log_index FROM logs WHERE block_number BETWEEN ",
+		);
+		builder.push_bind(from_block);
+		builder.push(" AND ");
+		builder.push_bind(to_block);
+
+		if !addresses.is_empty() {
+			builder.push(" AND address IN (");
+			let mut separated = builder.separated(", ");
+			for address in addresses.iter() {
+				separated.push_bind(address.clone());
+			}
+			separated.push_unseparated(")");
+		}
+
+		// Ethereum log filters carry at most four positional topics, matching
+		// the `topic_1..topic_4` columns. Reject anything longer rather than
+		// emitting a reference to a non-existent `topic_5` column.
+		if topics.len() > 4 {
+			return Err(Error::Protocol(format!(
+				"filter has {} topic positions, at most 4 are supported",
+				topics.len()
+			)));
+		}
+
+		for (position, topic) in topics.iter().enumerate() {
+			if topic.is_empty() {
+				continue;
+			}
+			builder.push(format!(" AND topic_{} IN (", position + 1));
+			let mut separated = builder.separated(", ");
+			for value in topic.iter() {
+				separated.push_bind(value.clone());
+			}
+			separated.push_unseparated(")");
+		}
+
+		builder.push(" ORDER BY block_number ASC, transaction_index ASC, log_index ASC");
+		// Fetch one extra row so we can distinguish "exactly at the cap" from
+		// "over the cap" without a separate COUNT query.
+		builder.push(" LIMIT ");
+		builder.push_bind(max_past_logs as i64 + 1);
+
+		let rows = builder.build().fetch_all(self.pool()).await?;
+		if rows.len() as u32 > max_past_logs {
+			return Err(Error::Protocol(format!(
+				"query returned more than {} results",
+				max_past_logs
+			)));
+		}
+
+		let mut logs = Vec::with_capacity(rows.len());
+		for row in rows.iter() {
+			logs.push(FilteredLog {
+				substrate_block_hash: H256::from_slice(&row.try_get::<Vec<u8>, _>(0)?[..]),
+				transaction_index: row.try_get(1)?,
+				log_index: row.try_get(2)?,
+			});
+		}
+		Ok(logs)
+	}
+
+	/// Read the backfill watermark as `(min_indexed, max_indexed)` heights, or
+	/// `None` if no backfill has run yet. `min_indexed` is the lowest canonical
+	/// height already enqueued, so a restart can resume the downward walk from
+	/// just below it instead of re-walking the whole chain.
+	pub async fn backfill_watermark(&self) -> Result<Option<(i32, i32)>, Error> {
+		let row = sqlx::query(
+			"SELECT min_indexed, max_indexed FROM backfill_progress WHERE id = 0",
+		)
+		.fetch_optional(self.pool())
+		.await?;
+		match row {
+			Some(row) => Ok(Some((row.try_get(0)?, row.try_get(1)?))),
+			None => Ok(None),
+		}
+	}
+
+	/// Persist the backfill watermark. The single-row table keeps the lowest
+	/// and highest canonical heights enqueued so far.
+	pub async fn update_backfill_watermark(
+		&self,
+		min_indexed: i32,
+		max_indexed: i32,
+	) -> Result<AnyQueryResult, Error> {
+		sqlx::query(
+			"INSERT INTO backfill_progress(id, min_indexed, max_indexed)
+             VALUES (0, $1, $2)
+             ON CONFLICT (id) DO UPDATE
+                SET min_indexed = excluded.min_indexed,
+                    max_indexed = excluded.max_indexed",
+		)
+		.bind(min_indexed)
+		.bind(max_indexed)
+		.execute(self.pool())
+		.await
+	}
+
 	pub fn spawn_logs_task<Client, BE>(&self, client: Arc<Client>, batch_size: usize)
 	where
 		Client: StorageProvider<Block, BE> + HeaderBackend<Block> + Send + Sync + 'static,
@@ -150,16 +332,48 @@ where
 							}
 						}
 						// Spawn a blocking task to get log data from substrate backend.
-						let logs = tokio::task::spawn_blocking(move || {
+						let indexed = tokio::task::spawn_blocking(move || {
 							Self::spawn_logs_task_inner(client.clone(), overrides, &to_index)
 						})
 						.await
 						.map_err(|_| Error::Protocol("tokio blocking task failed".to_string()))?;
 
+						for mapping in indexed.block_mappings.iter() {
+							let _ = sqlx::query(
+								"INSERT INTO block_mapping(
+							        ethereum_block_hash,
+							        substrate_block_hash)
+							    VALUES ($1, $2)
+							    ON CONFLICT DO NOTHING",
+							)
+							.bind(mapping.ethereum_block_hash.clone())
+							.bind(mapping.substrate_block_hash.clone())
+							.execute(&mut tx)
+							.await?;
+						}
+
+						for mapping in indexed.transaction_mappings.iter() {
+							let _ = sqlx::query(
+								"INSERT INTO transaction_mapping(
+							        ethereum_transaction_hash,
+							        substrate_block_hash,
+							        ethereum_block_hash,
+							        transaction_index)
+							    VALUES ($1, $2, $3, $4)
+							    ON CONFLICT DO NOTHING",
+							)
+							.bind(mapping.ethereum_transaction_hash.clone())
+							.bind(mapping.substrate_block_hash.clone())
+							.bind(mapping.ethereum_block_hash.clone())
+							.bind(mapping.transaction_index)
+							.execute(&mut tx)
+							.await?;
+						}
+
 						// TODO VERIFY statements limit per transaction in sqlite if any
-						for log in logs.iter() {
-							let _ = sqlx::query!(
-								"INSERT OR IGNORE INTO logs(
+						for log in indexed.logs.iter() {
+							let _ = sqlx::query(
+								"INSERT INTO logs(
 							        block_number,
 							        address,
 							        topic_1,
@@ -169,17 +383,18 @@ where
 							        log_index,
 							        transaction_index,
 							        substrate_block_hash)
-							    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
-								log.block_number,
-								log.address,
-								log.topic_1,
-								log.topic_2,
-								log.topic_3,
-								log.topic_4,
-								log.log_index,
-								log.transaction_index,
-								log.substrate_block_hash,
+							    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+							    ON CONFLICT DO NOTHING",
 							)
+							.bind(log.block_number)
+							.bind(log.address.clone())
+							.bind(log.topic_1.clone())
+							.bind(log.topic_2.clone())
+							.bind(log.topic_3.clone())
+							.bind(log.topic_4.clone())
+							.bind(log.log_index)
+							.bind(log.transaction_index)
+							.bind(log.substrate_block_hash.clone())
 							.execute(&mut tx)
 							.await?;
 						}
@@ -203,13 +418,13 @@ where
 		client: Arc<Client>,
 		overrides: Arc<OverrideHandle<Block>>,
 		hashes: &Vec<H256>,
-	) -> Vec<Log>
+	) -> IndexedBlocks
 	where
 		Client: StorageProvider<Block, BE> + HeaderBackend<Block> + Send + Sync + 'static,
 		BE: BackendT<Block> + 'static,
 		BE::State: StateBackend<BlakeTwo256>,
 	{
-		let mut logs: Vec<Log> = vec![];
+		let mut indexed = IndexedBlocks::default();
 		for substrate_block_hash in hashes.iter() {
 			let substrate_block_number: i32 =
 				if let Ok(Some(number)) = client.number(*substrate_block_hash) {
@@ -231,6 +446,25 @@ where
 
 			let receipts = handler.current_receipts(&id).unwrap_or_default();
 
+			// The ethereum block carries the header we hash for the block
+			// mapping and the transactions we hash for the transaction mapping.
+			if let Some(ethereum_block) = handler.current_block(&id) {
+				let ethereum_block_hash = ethereum_block.header.hash().as_bytes().to_owned();
+				indexed.block_mappings.push(BlockMapping {
+					ethereum_block_hash: ethereum_block_hash.clone(),
+					substrate_block_hash: substrate_block_hash.as_bytes().to_owned(),
+				});
+				for (transaction_index, transaction) in ethereum_block.transactions.iter().enumerate()
+				{
+					indexed.transaction_mappings.push(TransactionMapping {
+						ethereum_transaction_hash: transaction.hash().as_bytes().to_owned(),
+						substrate_block_hash: substrate_block_hash.as_bytes().to_owned(),
+						ethereum_block_hash: ethereum_block_hash.clone(),
+						transaction_index: transaction_index as i32,
+					});
+				}
+			}
+
 			for (transaction_index, receipt) in receipts.iter().enumerate() {
 				let receipt_logs = match receipt {
 					ethereum::ReceiptV3::Legacy(d)
@@ -239,7 +473,7 @@ where
 				};
 				let transaction_index = transaction_index as i32;
 				for (log_index, log) in receipt_logs.iter().enumerate() {
-					logs.push(Log {
+					indexed.logs.push(Log {
 						block_number: substrate_block_number,
 						address: log.address.as_bytes().to_owned(),
 						topic_1: log
@@ -273,7 +507,7 @@ where
 				}
 			}
 		}
-		logs
+		indexed
 	}
 
 	fn onchain_storage_schema<Client, BE>(
@@ -296,20 +530,29 @@ where
 		}
 	}
 
-	async fn create_if_not_exists(pool: &SqlitePool) -> Result<SqliteQueryResult, Error> {
-		sqlx::query(
-			"BEGIN;
-            CREATE TABLE IF NOT EXISTS logs (
-                id INTEGER PRIMARY KEY,
-                block_number INTEGER NOT NULL,
-                address BLOB NOT NULL,
-                topic_1 BLOB NOT NULL,
-                topic_2 BLOB NOT NULL,
-                topic_3 BLOB NOT NULL,
-                topic_4 BLOB NOT NULL,
-                log_index INTEGER NOT NULL,
-                transaction_index INTEGER NOT NULL,
-                substrate_block_hash BLOB NOT NULL,
+	async fn create_if_not_exists(pool: &AnyPool, is_postgres: bool) -> Result<(), Error> {
+		// Portable DDL: the two backends spell auto-increment primary keys and
+		// byte columns differently, so those concrete types are substituted per
+		// backend while the schema stays identical. Integer columns are
+		// declared `INTEGER` (32-bit / `int4` on Postgres) on both backends to
+		// match the `i32` bound and decoded everywhere in the read paths.
+		let (pk, blob, int) = if is_postgres {
+			("SERIAL PRIMARY KEY", "BYTEA", "INTEGER")
+		} else {
+			("INTEGER PRIMARY KEY", "BLOB", "INTEGER")
+		};
+		let ddl = format!(
+			"CREATE TABLE IF NOT EXISTS logs (
+                id {pk},
+                block_number {int} NOT NULL,
+                address {blob} NOT NULL,
+                topic_1 {blob} NOT NULL,
+                topic_2 {blob} NOT NULL,
+                topic_3 {blob} NOT NULL,
+                topic_4 {blob} NOT NULL,
+                log_index {int} NOT NULL,
+                transaction_index {int} NOT NULL,
+                substrate_block_hash {blob} NOT NULL,
 				UNIQUE (
                     log_index,
                     transaction_index,
@@ -317,13 +560,53 @@ where
                 )
             );
             CREATE TABLE IF NOT EXISTS sync_status (
-                id INTEGER PRIMARY KEY,
-                substrate_block_hash BLOB NOT NULL,
-                status INTEGER DEFAULT 0 NOT NULL,
+                id {pk},
+                substrate_block_hash {blob} NOT NULL,
+                status {int} DEFAULT 0 NOT NULL,
+				UNIQUE (
+                    substrate_block_hash
+                )
+            );
+            CREATE TABLE IF NOT EXISTS block_mapping (
+                id {pk},
+                ethereum_block_hash {blob} NOT NULL,
+                substrate_block_hash {blob} NOT NULL,
 				UNIQUE (
+                    ethereum_block_hash,
                     substrate_block_hash
                 )
             );
+            CREATE TABLE IF NOT EXISTS transaction_mapping (
+                id {pk},
+                ethereum_transaction_hash {blob} NOT NULL,
+                substrate_block_hash {blob} NOT NULL,
+                ethereum_block_hash {blob} NOT NULL,
+                transaction_index {int} NOT NULL,
+				UNIQUE (
+                    ethereum_transaction_hash,
+                    substrate_block_hash
+                )
+            );
+            CREATE TABLE IF NOT EXISTS backfill_progress (
+                id {int} PRIMARY KEY,
+                min_indexed {int} NOT NULL,
+                max_indexed {int} NOT NULL
+            );
+            -- `CREATE TABLE IF NOT EXISTS` above leaves a `sync_status` table
+            -- created by a pre-existing binary without the inline `UNIQUE`
+            -- constraint, so `insert_sync_status`'s `ON CONFLICT DO NOTHING`
+            -- has nothing to match against and silently re-enqueues hashes on
+            -- every restart. Add the index explicitly so it lands on an
+            -- upgrade even when the table already exists.
+            CREATE UNIQUE INDEX IF NOT EXISTS sync_status_substrate_block_hash_idx ON sync_status (
+                substrate_block_hash
+            );
+            CREATE INDEX IF NOT EXISTS ethereum_block_hash_idx ON block_mapping (
+                ethereum_block_hash
+            );
+            CREATE INDEX IF NOT EXISTS ethereum_transaction_hash_idx ON transaction_mapping (
+                ethereum_transaction_hash
+            );
             CREATE INDEX IF NOT EXISTS block_number_idx ON logs (
                 block_number,
                 address
@@ -343,22 +626,324 @@ where
             CREATE INDEX IF NOT EXISTS topic_4_idx ON logs (
                 block_number,
                 topic_4
-            );
-            COMMIT;",
-		)
-		.execute(pool)
-		.await
+            );"
+		);
+
+		// The `Any`/Postgres driver speaks the extended (prepared-statement)
+		// protocol, which — unlike SQLite's simple-query execution — rejects a
+		// single query string containing more than one statement. Split the
+		// DDL on `;` and run each statement as its own prepared query inside
+		// one transaction, so schema creation stays atomic on both backends.
+		let mut tx = pool.begin().await?;
+		for statement in ddl.split(';') {
+			let statement = statement.trim();
+			if statement.is_empty() {
+				continue;
+			}
+			sqlx::query(statement).execute(&mut tx).await?;
+		}
+		tx.commit().await?;
+		Ok(())
 	}
 }
 
-impl<Block: BlockT> crate::BackendReader<Block> for Backend<Block> {
+/// Drive a query future to completion from a synchronous context.
+///
+/// These reads are reached from the synchronous [`crate::BackendReader`] trait
+/// methods. We use `futures::executor::block_on` to match fc-db's other
+/// synchronous read paths; unlike driving the current tokio handle, it does not
+/// panic when invoked off a multi-threaded tokio worker (current-thread runtime
+/// or no runtime in scope).
+///
+/// This only drives futures to completion on its own; it runs no tokio I/O
+/// driver. That's fine for SQLite, whose driver hands off to a blocking
+/// threadpool, but not for Postgres, whose driver polls a socket through
+/// tokio's reactor — a future built on it would never be woken and this would
+/// hang forever. Callers on the `BackendReader` impl below reject the
+/// Postgres backend before reaching this function rather than risk that hang.
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+	futures::executor::block_on(future)
+}
+
+/// `BackendReader`'s sync reads only work safely against SQLite; see
+/// [`block_on`].
+const POSTGRES_SYNC_READ_ERR: &str =
+	"SQL backend: synchronous mapping reads are only supported against SQLite; \
+	 the Postgres driver requires a tokio reactor that a bare `block_on` does not drive";
+
+impl<Block: BlockT> crate::BackendReader<Block> for Backend<Block>
+where
+	Block: BlockT<Hash = H256>,
+{
 	fn block_hash(&self, ethereum_block_hash: &H256) -> Result<Option<Vec<Block::Hash>>, String> {
-		todo!()
+		if self.is_postgres {
+			return Err(POSTGRES_SYNC_READ_ERR.to_string());
+		}
+		let ethereum_block_hash = ethereum_block_hash.as_bytes().to_owned();
+		block_on(async {
+			let rows = sqlx::query(
+				"SELECT substrate_block_hash FROM block_mapping WHERE ethereum_block_hash = $1",
+			)
+			.bind(ethereum_block_hash)
+			.fetch_all(self.pool())
+			.await
+			.map_err(|e| format!("{:?}", e))?;
+			let mut out = Vec::with_capacity(rows.len());
+			for row in rows.iter() {
+				let bytes = row
+					.try_get::<Vec<u8>, _>(0)
+					.map_err(|e| format!("{:?}", e))?;
+				out.push(H256::from_slice(&bytes[..]));
+			}
+			Ok(if out.is_empty() { None } else { Some(out) })
+		})
 	}
 	fn transaction_metadata(
 		&self,
 		ethereum_transaction_hash: &H256,
 	) -> Result<Vec<crate::TransactionMetadata<Block>>, String> {
-		todo!()
+		if self.is_postgres {
+			return Err(POSTGRES_SYNC_READ_ERR.to_string());
+		}
+		let ethereum_transaction_hash = ethereum_transaction_hash.as_bytes().to_owned();
+		block_on(async {
+			let rows = sqlx::query(
+				"SELECT substrate_block_hash, ethereum_block_hash, transaction_index
+                 FROM transaction_mapping
+                 WHERE ethereum_transaction_hash = $1",
+			)
+			.bind(ethereum_transaction_hash)
+			.fetch_all(self.pool())
+			.await
+			.map_err(|e| format!("{:?}", e))?;
+			let mut metadata = Vec::with_capacity(rows.len());
+			for row in rows.iter() {
+				let substrate_block_hash = H256::from_slice(
+					&row.try_get::<Vec<u8>, _>(0)
+						.map_err(|e| format!("{:?}", e))?[..],
+				);
+				let ethereum_block_hash = H256::from_slice(
+					&row.try_get::<Vec<u8>, _>(1)
+						.map_err(|e| format!("{:?}", e))?[..],
+				);
+				let ethereum_index: i32 =
+					row.try_get(2).map_err(|e| format!("{:?}", e))?;
+				metadata.push(crate::TransactionMetadata::<Block> {
+					substrate_block_hash,
+					ethereum_block_hash,
+					ethereum_index: ethereum_index as u32,
+				});
+			}
+			Ok(metadata)
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::H160;
+	use sp_runtime::{
+		generic::{Block as RawBlock, Header},
+		Permill,
+	};
+	use tempfile::tempdir;
+
+	type OpaqueBlock = RawBlock<Header<u64, BlakeTwo256>, sp_runtime::OpaqueExtrinsic>;
+
+	/// Test fallback: the query paths exercised here (`filter_logs`, the
+	/// backfill watermark and the mapping reads) never touch the schema
+	/// overrides, so every method is unreachable.
+	struct NoopOverride;
+
+	impl fp_storage::StorageOverride<OpaqueBlock> for NoopOverride {
+		fn account_code_at(&self, _: &BlockId<OpaqueBlock>, _: H160) -> Option<Vec<u8>> {
+			unreachable!("overrides are not used by the SQL read paths under test")
+		}
+		fn account_storage_at(
+			&self,
+			_: &BlockId<OpaqueBlock>,
+			_: H160,
+			_: H256,
+		) -> Option<H256> {
+			unreachable!("overrides are not used by the SQL read paths under test")
+		}
+		fn current_block(&self, _: &BlockId<OpaqueBlock>) -> Option<ethereum::BlockV2> {
+			unreachable!("overrides are not used by the SQL read paths under test")
+		}
+		fn current_receipts(&self, _: &BlockId<OpaqueBlock>) -> Option<Vec<ethereum::ReceiptV3>> {
+			unreachable!("overrides are not used by the SQL read paths under test")
+		}
+		fn current_transaction_statuses(
+			&self,
+			_: &BlockId<OpaqueBlock>,
+		) -> Option<Vec<fp_rpc::TransactionStatus>> {
+			unreachable!("overrides are not used by the SQL read paths under test")
+		}
+		fn elasticity(&self, _: &BlockId<OpaqueBlock>) -> Option<Permill> {
+			unreachable!("overrides are not used by the SQL read paths under test")
+		}
+		fn is_eip1559(&self, _: &BlockId<OpaqueBlock>) -> bool {
+			unreachable!("overrides are not used by the SQL read paths under test")
+		}
+	}
+
+	/// Open a fresh SQLite-backed `Backend` under a temporary directory, with
+	/// the schema created.
+	async fn test_backend() -> (tempfile::TempDir, Backend<OpaqueBlock>) {
+		let tmp = tempdir().expect("create tempdir");
+		let path = format!("sqlite:///{}/test.db3", tmp.path().display());
+		let overrides = Arc::new(OverrideHandle {
+			schemas: Default::default(),
+			fallback: Box::new(NoopOverride),
+		});
+		let backend = Backend::new(
+			BackendConfig::Sqlite(SqliteBackendConfig {
+				path: &path,
+				create_if_missing: true,
+			}),
+			1,
+			overrides,
+		)
+		.await
+		.expect("open sqlite backend");
+		(tmp, backend)
+	}
+
+	async fn insert_log(
+		backend: &Backend<OpaqueBlock>,
+		block_number: i32,
+		transaction_index: i32,
+		log_index: i32,
+		address: H160,
+		topic_1: H256,
+		substrate_block_hash: H256,
+	) {
+		sqlx::query(
+			"INSERT INTO logs(
+                block_number, address, topic_1, topic_2, topic_3, topic_4,
+                log_index, transaction_index, substrate_block_hash)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+		)
+		.bind(block_number)
+		.bind(address.as_bytes().to_vec())
+		.bind(topic_1.as_bytes().to_vec())
+		.bind(H256::zero().as_bytes().to_vec())
+		.bind(H256::zero().as_bytes().to_vec())
+		.bind(H256::zero().as_bytes().to_vec())
+		.bind(log_index)
+		.bind(transaction_index)
+		.bind(substrate_block_hash.as_bytes().to_vec())
+		.execute(backend.pool())
+		.await
+		.expect("insert log");
+	}
+
+	#[tokio::test]
+	async fn filter_logs_filters_by_address_and_topic() {
+		let (_tmp, backend) = test_backend().await;
+		let addr_a = H160::repeat_byte(0x11);
+		let addr_b = H160::repeat_byte(0x22);
+		let topic = H256::repeat_byte(0xaa);
+		let block_hash = H256::repeat_byte(0x01);
+
+		insert_log(&backend, 1, 0, 0, addr_a, topic, block_hash).await;
+		insert_log(&backend, 1, 0, 1, addr_b, H256::repeat_byte(0xbb), block_hash).await;
+		insert_log(&backend, 2, 0, 0, addr_a, H256::repeat_byte(0xcc), H256::repeat_byte(0x02)).await;
+
+		// Address set narrows the range scan to `addr_a`'s two logs.
+		let by_address = backend
+			.filter_logs(0, 10, vec![addr_a.as_bytes().to_vec()], vec![], 100)
+			.await
+			.expect("filter by address");
+		assert_eq!(by_address.len(), 2);
+
+		// Adding a positional topic set narrows further to the single match.
+		let by_topic = backend
+			.filter_logs(
+				0,
+				10,
+				vec![addr_a.as_bytes().to_vec()],
+				vec![vec![topic.as_bytes().to_vec()]],
+				100,
+			)
+			.await
+			.expect("filter by topic");
+		assert_eq!(by_topic.len(), 1);
+		assert_eq!(by_topic[0].log_index, 0);
+		assert_eq!(by_topic[0].transaction_index, 0);
+	}
+
+	#[tokio::test]
+	async fn filter_logs_rejects_more_than_four_topic_positions() {
+		let (_tmp, backend) = test_backend().await;
+		let topics = vec![vec![H256::zero().as_bytes().to_vec()]; 5];
+		let result = backend.filter_logs(0, 10, vec![], topics, 100).await;
+		assert!(matches!(result, Err(Error::Protocol(_))));
+	}
+
+	#[tokio::test]
+	async fn filter_logs_caps_result_count() {
+		let (_tmp, backend) = test_backend().await;
+		let addr = H160::repeat_byte(0x11);
+		let block_hash = H256::repeat_byte(0x01);
+		for i in 0..3 {
+			insert_log(&backend, 1, 0, i, addr, H256::repeat_byte(0xaa), block_hash).await;
+		}
+
+		// At the cap everything is returned.
+		let exact = backend
+			.filter_logs(0, 10, vec![], vec![], 3)
+			.await
+			.expect("at cap");
+		assert_eq!(exact.len(), 3);
+
+		// One over the cap is an error, not a silent truncation.
+		let over = backend.filter_logs(0, 10, vec![], vec![], 2).await;
+		assert!(matches!(over, Err(Error::Protocol(_))));
+	}
+
+	#[tokio::test]
+	async fn backfill_watermark_round_trips() {
+		let (_tmp, backend) = test_backend().await;
+		assert_eq!(backend.backfill_watermark().await.unwrap(), None);
+
+		backend.update_backfill_watermark(100, 200).await.unwrap();
+		assert_eq!(backend.backfill_watermark().await.unwrap(), Some((100, 200)));
+
+		// The single-row table is upserted in place.
+		backend.update_backfill_watermark(50, 250).await.unwrap();
+		assert_eq!(backend.backfill_watermark().await.unwrap(), Some((50, 250)));
+	}
+
+	#[tokio::test]
+	async fn transaction_metadata_reads_mapping() {
+		use crate::BackendReader;
+
+		let (_tmp, backend) = test_backend().await;
+		let eth_tx = H256::repeat_byte(0x77);
+		let eth_block = H256::repeat_byte(0x88);
+		let substrate_block = H256::repeat_byte(0x99);
+
+		sqlx::query(
+			"INSERT INTO transaction_mapping(
+                ethereum_transaction_hash, substrate_block_hash,
+                ethereum_block_hash, transaction_index)
+             VALUES ($1, $2, $3, $4)",
+		)
+		.bind(eth_tx.as_bytes().to_vec())
+		.bind(substrate_block.as_bytes().to_vec())
+		.bind(eth_block.as_bytes().to_vec())
+		.bind(5i32)
+		.execute(backend.pool())
+		.await
+		.unwrap();
+
+		let metadata = BackendReader::<OpaqueBlock>::transaction_metadata(&backend, &eth_tx)
+			.expect("read transaction metadata");
+		assert_eq!(metadata.len(), 1);
+		assert_eq!(metadata[0].substrate_block_hash, substrate_block);
+		assert_eq!(metadata[0].ethereum_block_hash, eth_block);
+		assert_eq!(metadata[0].ethereum_index, 5);
 	}
 }
\ No newline at end of file
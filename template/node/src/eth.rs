@@ -11,7 +11,9 @@ use sc_client_api::{BlockchainEvents, StateBackendFor};
 use sc_executor::NativeExecutionDispatch;
 use sc_service::{error::Error as ServiceError, BasePath, Configuration, TaskManager};
 use sp_api::ConstructRuntimeApi;
-use sp_runtime::traits::BlakeTwo256;
+use sp_blockchain::HeaderBackend;
+use sp_core::H256;
+use sp_runtime::traits::{BlakeTwo256, NumberFor, UniqueSaturatedFrom, UniqueSaturatedInto};
 // Frontier
 pub use fc_consensus::FrontierBlockImport;
 use fc_rpc::{EthTask, OverrideHandle};
@@ -88,6 +90,39 @@ pub struct EthConfiguration {
 	/// Sets the SQL backend's query timeout in number of VM ops.
 	#[arg(long, default_value = "10000000")]
 	pub frontier_sql_backend_num_ops_timeout: u32,
+
+	/// Sets a Postgres connection URL for the SQL backend. When provided, the
+	/// SQL backend connects to this external, concurrently-readable database
+	/// instead of the embedded SQLite file.
+	#[arg(long)]
+	pub frontier_sql_backend_postgres_url: Option<String>,
+
+	/// When using the SQL backend, backfill the log index from this block down
+	/// to genesis on startup. Defaults to the current best block, so a node
+	/// that switches to `--frontier-backend-type sql` after syncing still
+	/// indexes the chain it already holds. Only read when no backfill
+	/// watermark has been recorded yet; later startups resume from the
+	/// recorded watermark and ignore this value.
+	#[arg(long)]
+	pub frontier_sql_backfill_from: Option<u32>,
+}
+
+/// Build the SQL backend configuration from the node's eth configuration.
+///
+/// When `--frontier-sql-backend-postgres-url` is set the indexer targets that
+/// external, concurrently-readable Postgres database; otherwise it falls back
+/// to the embedded SQLite file under the node's database directory.
+pub fn sql_backend_config<'a>(
+	eth_config: &'a EthConfiguration,
+	sqlite_path: &'a str,
+) -> fc_db::sql::BackendConfig<'a> {
+	match &eth_config.frontier_sql_backend_postgres_url {
+		Some(url) => fc_db::sql::BackendConfig::Postgres(fc_db::sql::PostgresBackendConfig { url }),
+		None => fc_db::sql::BackendConfig::Sqlite(fc_db::sql::SqliteBackendConfig {
+			path: sqlite_path,
+			create_if_missing: true,
+		}),
+	}
 }
 
 pub struct FrontierPartialComponents {
@@ -125,6 +160,12 @@ where
 {
 }
 
+/// Spawn Frontier's background maintenance tasks on `task_manager`.
+///
+/// `sql_backfill_from` is taken straight from [`EthConfiguration`]; the call
+/// site in `template/node/src/service.rs` needs updating to pass it whenever
+/// this function's parameter list changes — this module has no caller of its
+/// own to keep in sync.
 pub async fn spawn_frontier_tasks<RuntimeApi, Executor>(
 	task_manager: &TaskManager,
 	client: Arc<FullClient<RuntimeApi, Executor>>,
@@ -134,6 +175,7 @@ pub async fn spawn_frontier_tasks<RuntimeApi, Executor>(
 	overrides: Arc<OverrideHandle<Block>>,
 	fee_history_cache: FeeHistoryCache,
 	fee_history_cache_limit: FeeHistoryCacheLimit,
+	sql_backfill_from: Option<u32>,
 ) where
 	RuntimeApi: ConstructRuntimeApi<Block, FullClient<RuntimeApi, Executor>>,
 	RuntimeApi: Send + Sync + 'static,
@@ -161,18 +203,30 @@ pub async fn spawn_frontier_tasks<RuntimeApi, Executor>(
 			);
 		}
 		fc_db::Backend::Sql(b) => {
+			let sql_backend = Arc::new(b);
 			task_manager.spawn_essential_handle().spawn(
 				"frontier-mapping-sync-worker",
 				None,
 				fc_mapping_sync::sql::SyncWorker::run(
 					client.clone(),
 					backend,
-					Arc::new(b),
+					sql_backend.clone(),
 					client.import_notification_stream(),
 					1000,                              // batch size
 					std::time::Duration::from_secs(1), // interval duration
 				),
 			);
+			// Live import notifications only cover blocks imported from now on.
+			// Walk the canonical chain backwards and enqueue historical blocks
+			// so already-synced and archive nodes get a complete index. Unlike
+			// the other tasks here, this one is finite — it returns once the
+			// descent reaches genesis — so it must not be spawned essential:
+			// an essential task completing tears down the whole node.
+			task_manager.spawn_handle().spawn(
+				"frontier-sql-backfill",
+				Some("frontier"),
+				backfill_sql_index(client.clone(), sql_backend, sql_backfill_from, 1000),
+			);
 		}
 	}
 
@@ -192,10 +246,165 @@ pub async fn spawn_frontier_tasks<RuntimeApi, Executor>(
 		"frontier-fee-history",
 		Some("frontier"),
 		EthTask::fee_history_task(
-			client,
+			client.clone(),
 			overrides,
 			fee_history_cache,
 			fee_history_cache_limit,
 		),
 	);
 }
+
+/// Backfill the SQL log index by walking the canonical chain downwards,
+/// enqueuing substrate block hashes into the `sync_status` table in batches for
+/// `spawn_logs_task` to drain.
+///
+/// The walk resumes from a persisted `(min_indexed, max_indexed)` watermark: on
+/// the first run it starts at `backfill_from` (or the current best block) and
+/// descends to genesis; on a restart it first enqueues any blocks that have
+/// appeared above the previously recorded `max_indexed` (the tip grew while the
+/// node was down and the live stream missed them), then continues downwards
+/// from just below `min_indexed` rather than re-walking the whole chain — so
+/// boot cost is proportional to the work left, not to chain length. The
+/// watermark is advanced after every batch, and the descent yields to the
+/// executor periodically so live tip indexing is never starved.
+async fn backfill_sql_index<Client>(
+	client: Arc<Client>,
+	backend: Arc<fc_db::sql::Backend<Block>>,
+	backfill_from: Option<u32>,
+	batch_size: usize,
+) where
+	Client: HeaderBackend<Block> + 'static,
+{
+	// Yield to the executor at least this often during the header descent so a
+	// long backfill never monopolizes the task between batch flushes.
+	const YIELD_EVERY: u32 = 64;
+
+	let best: u32 = client.info().best_number.unique_saturated_into();
+	let requested_top = backfill_from.unwrap_or(best);
+
+	// Resume from the persisted watermark when present.
+	let watermark = match backend.backfill_watermark().await {
+		Ok(watermark) => watermark,
+		Err(e) => {
+			log::error!(target: "eth-log-indexer", "failed to read backfill watermark: {:?}", e);
+			return;
+		}
+	};
+
+	// The range still to walk downwards, and the highest height covered so far.
+	let (mut height, mut max_indexed) = match watermark {
+		Some((min_indexed, max_indexed)) => {
+			// The tip may have advanced past the recorded top while we were
+			// down; cover the gap `[max_indexed + 1, best]` before descending.
+			if best as i32 > max_indexed {
+				let top = best;
+				let bottom = (max_indexed + 1) as u32;
+				enqueue_range(&client, &backend, top, bottom, batch_size, YIELD_EVERY).await;
+				max_indexed = best as i32;
+				if let Err(e) = backend.update_backfill_watermark(min_indexed, max_indexed).await {
+					log::error!(target: "eth-log-indexer", "failed to persist backfill watermark: {:?}", e);
+				}
+			}
+			if min_indexed <= 0 {
+				// Already walked down to genesis on a previous run.
+				log::info!(target: "eth-log-indexer", "SQL backend backfill already complete");
+				return;
+			}
+			((min_indexed as u32).saturating_sub(1), max_indexed)
+		}
+		None => (requested_top, requested_top as i32),
+	};
+
+	let mut batch: Vec<H256> = Vec::with_capacity(batch_size);
+	let flush = |batch: &mut Vec<H256>| {
+		let backend = backend.clone();
+		let drained = std::mem::take(batch);
+		async move {
+			if let Err(e) = backend.insert_sync_status(&drained).await {
+				log::error!(
+					target: "eth-log-indexer",
+					"failed to enqueue backfill batch: {:?}",
+					e
+				);
+			}
+		}
+	};
+
+	loop {
+		if let Ok(Some(hash)) = client.hash(NumberFor::<Block>::unique_saturated_from(height)) {
+			batch.push(hash);
+		}
+		let at_genesis = height == 0;
+		if batch.len() >= batch_size || at_genesis {
+			if !batch.is_empty() {
+				flush(&mut batch).await;
+				// Advance the watermark so a restart resumes from here.
+				if let Err(e) = backend.update_backfill_watermark(height as i32, max_indexed).await {
+					log::error!(
+						target: "eth-log-indexer",
+						"failed to persist backfill watermark: {:?}",
+						e
+					);
+				}
+				// Yield so live import-notification indexing is not starved.
+				futures_timer::Delay::new(Duration::from_millis(100)).await;
+			}
+		} else if height % YIELD_EVERY == 0 {
+			// Yield between flushes too, so a 1000-block batch of header
+			// lookups doesn't hold the task for its whole duration.
+			futures_timer::Delay::new(Duration::from_millis(0)).await;
+		}
+		if at_genesis {
+			break;
+		}
+		height -= 1;
+	}
+	log::info!(
+		target: "eth-log-indexer",
+		"SQL backend backfill enqueue complete"
+	);
+}
+
+/// Enqueue the inclusive height range `[bottom, top]` (walked high to low) for
+/// SQL indexing, flushing in `batch_size` chunks and yielding periodically.
+///
+/// Used on resume to cover blocks that appeared above the recorded watermark
+/// while the node was down; it does not touch the watermark itself.
+async fn enqueue_range<Client>(
+	client: &Arc<Client>,
+	backend: &Arc<fc_db::sql::Backend<Block>>,
+	top: u32,
+	bottom: u32,
+	batch_size: usize,
+	yield_every: u32,
+) where
+	Client: HeaderBackend<Block> + 'static,
+{
+	let mut batch: Vec<H256> = Vec::with_capacity(batch_size);
+	let mut height = top;
+	loop {
+		if let Ok(Some(hash)) = client.hash(NumberFor::<Block>::unique_saturated_from(height)) {
+			batch.push(hash);
+		}
+		let at_bottom = height == bottom;
+		if batch.len() >= batch_size || at_bottom {
+			if !batch.is_empty() {
+				let drained = std::mem::take(&mut batch);
+				if let Err(e) = backend.insert_sync_status(&drained).await {
+					log::error!(
+						target: "eth-log-indexer",
+						"failed to enqueue backfill batch: {:?}",
+						e
+					);
+				}
+				futures_timer::Delay::new(Duration::from_millis(100)).await;
+			}
+		} else if height % yield_every == 0 {
+			futures_timer::Delay::new(Duration::from_millis(0)).await;
+		}
+		if at_bottom {
+			break;
+		}
+		height -= 1;
+	}
+}